@@ -8,16 +8,34 @@ use winit::{
   dpi::LogicalSize
 };
 
+use cfg_if::cfg_if;
+
+#[cfg(target_arch = "wasm32")]
+use wasm_bindgen::prelude::*;
+#[cfg(target_arch = "wasm32")]
+use winit::platform::web::WindowExtWebSys;
+
 async fn run() {
 
   // create a window
   let event_loop = EventLoop::new();
   let window = WindowBuilder::new().with_inner_size(LogicalSize::new(300, 300)).with_title("tic tac toe: cross turn").with_resizable(false).build(&event_loop).unwrap();
 
+  // on the web, the canvas isn't attached to the document by default, so we have to do it ourselves
+  cfg_if! {
+    if #[cfg(target_arch = "wasm32")] {
+      web_sys::window()
+        .and_then(|win| win.document())
+        .and_then(|doc| doc.body())
+        .and_then(|body| body.append_child(&web_sys::Element::from(window.canvas())).ok())
+        .expect("Couldn't append canvas to document body");
+    }
+  }
+
   // get the renderer and gameboard setup
   let mut board = state::gen_board();
   let mut state = state::State::new();
-  let mut renderer = render::Render::new(&window, &mut board).await;
+  let mut renderer = render::Render::new(&window, &board).await;
   
   // create a variable to keep track of mouse pos
   let mut mouse_pos: winit::dpi::PhysicalPosition<f64> = winit::dpi::PhysicalPosition::new(0., 0.);
@@ -28,8 +46,9 @@ async fn run() {
 
       // rerender
       Event::RedrawRequested(_) => {
-        renderer.update(&mut board);
-        renderer.render();
+        state.tick_animations(&mut board);
+        renderer.update(&board);
+        renderer.render(state.winning_line());
       },
 
       // request redraw
@@ -40,6 +59,11 @@ async fn run() {
       // close the window
       Event::WindowEvent { event: WindowEvent::CloseRequested, .. } => *control_flow = ControlFlow::Exit,
 
+      // the surface changed size (a resizable window, or a DPI change) - rebuild the swap chain
+      Event::WindowEvent { event: WindowEvent::Resized(new_size), .. } => renderer.resize(new_size),
+
+      Event::WindowEvent { event: WindowEvent::ScaleFactorChanged { new_inner_size, .. }, .. } => renderer.resize(*new_inner_size),
+
       // mouse has moved! Keep track
       Event::WindowEvent { event: WindowEvent::CursorMoved { position, .. }, .. } => mouse_pos = position,
 
@@ -54,7 +78,17 @@ async fn run() {
 
 }
 
+// native entry point
+#[cfg(not(target_arch = "wasm32"))]
 fn main() {
-  // TODO add web version for web compat :)
   futures::executor::block_on(run());
 }
+
+// web entry point: wasm can't block on a future, so we hand it off to the browser's microtask queue
+#[cfg(target_arch = "wasm32")]
+#[wasm_bindgen(start)]
+pub fn main() {
+  std::panic::set_hook(Box::new(console_error_panic_hook::hook));
+  console_log::init_with_level(log::Level::Warn).expect("Couldn't initialize logger");
+  wasm_bindgen_futures::spawn_local(run());
+}
@@ -0,0 +1,344 @@
+use crate::state;
+
+mod pass;
+pub use pass::RenderGraph;
+use pass::{RenderContext, BoardPass, WinLinePass};
+
+use std::borrow::Cow;
+use bytemuck::{Pod, Zeroable};
+use wgpu::util::DeviceExt;
+use std::convert::TryInto;
+
+// one instance per board tile, plus one for the background
+const MAX_INSTANCES: usize = 10;
+
+/**
+ * a corner of the shared unit quad: a local position and the matching uv fraction within
+ * whichever spritesheet cell the instance picks
+ */
+#[repr(C)]
+#[derive(Clone, Copy, Pod, Zeroable)]
+pub struct Vertex {
+  pub pos: [f32; 2],
+  pub tex_coords: [f32; 2]
+}
+
+/**
+ * per-instance data for one board cell: where to place the shared quad, and which cell of the
+ * spritesheet to sample. `state` doubles as the BACKGROUND_STATE sentinel for the background instance.
+ */
+#[repr(C)]
+#[derive(Clone, Copy, Pod, Zeroable)]
+pub struct Instance {
+  pub offset: [f32; 2],
+  pub tex_origin: [f32; 2],
+  pub frame: u32,
+  pub state: u32
+}
+
+/**
+ * the shared unit quad every instance is drawn with: a single tile-sized square, scaled up to
+ * cover the whole board for the background instance (see shader.wgsl). Four unique corners,
+ * indexed as two triangles, rather than six vertices with two corners duplicated.
+ */
+fn gen_quad_vertices() -> (Vec<Vertex>, Vec<u16>) {
+  let vertices = vec![
+    Vertex { pos: [ 0., 0. ], tex_coords: [ 0., 0. ] },  // 0: top left
+    Vertex { pos: [ 0., -1. ], tex_coords: [ 0., 1. ] }, // 1: bottom left
+    Vertex { pos: [ 1., -1. ], tex_coords: [ 1., 1. ] }, // 2: bottom right
+    Vertex { pos: [ 1., 0. ], tex_coords: [ 1., 0. ] }   // 3: top right
+  ];
+  let indices: Vec<u16> = vec![ 0, 1, 2, 0, 2, 3 ];
+
+  return (vertices, indices);
+}
+
+/**
+ * Managing state for rendering
+ */
+pub struct Render {
+  pub surface: wgpu::Surface,
+  pub device: wgpu::Device,
+  pub queue: wgpu::Queue,
+  pub render_pipeline: wgpu::RenderPipeline,
+  pub sc_desc: wgpu::SwapChainDescriptor,
+  pub swap_chain: wgpu::SwapChain,
+  pub swapchain_format: wgpu::TextureFormat,
+
+  pub vertex_buf: wgpu::Buffer,
+  pub index_buf: wgpu::Buffer,
+  pub index_count: u32,
+  pub instance_buf: wgpu::Buffer,
+  pub instance_count: u32,
+  pub bind_group: wgpu::BindGroup,
+
+  pub graph: RenderGraph
+}
+
+impl Render {
+
+  pub async fn new(window: &winit::window::Window, board: &[[state::Tile; 3]; 3]) -> Self {
+
+    // lets get going besties
+    let size = window.inner_size();
+    let instance = wgpu::Instance::new(wgpu::BackendBit::all());
+    let surface = unsafe { instance.create_surface(window) };
+    let adapter = instance.request_adapter(&wgpu::RequestAdapterOptions {
+      power_preference: wgpu::PowerPreference::default(),
+      compatible_surface: Some(&surface)
+    }).await.expect("Failed to find an appropriate adapter");
+    // create device. WebGL2 can't meet the default limits, so ask for the downlevel web defaults there
+    cfg_if::cfg_if! {
+      if #[cfg(target_arch = "wasm32")] {
+        let limits = wgpu::Limits::downlevel_webgl2_defaults();
+      } else {
+        let limits = wgpu::Limits::default();
+      }
+    }
+    let (device, queue) = adapter.request_device(&wgpu::DeviceDescriptor {
+      label: Some("Device"),
+      features: wgpu::Features::empty(),
+      limits
+    }, None).await.expect("Failed to create device");
+
+    // create the shader module
+    let shader = device.create_shader_module(&wgpu::ShaderModuleDescriptor {
+      label: None,
+      source: wgpu::ShaderSource::Wgsl(Cow::Borrowed(include_str!("shader.wgsl"))),
+      flags: wgpu::ShaderFlags::VALIDATION
+    });
+
+    // the quad never changes, so it's created once here and reused for every instance
+    let (quad_vertices, quad_indices) = gen_quad_vertices();
+    let index_count = quad_indices.len() as u32;
+    let vertex_buf = device.create_buffer_init(&wgpu::util::BufferInitDescriptor {
+      label: Some("Vertex Buffer"),
+      contents: bytemuck::cast_slice(&quad_vertices),
+      usage: wgpu::BufferUsage::VERTEX
+    });
+    let index_buf = device.create_buffer_init(&wgpu::util::BufferInitDescriptor {
+      label: Some("Index Buffer"),
+      contents: bytemuck::cast_slice(&quad_indices),
+      usage: wgpu::BufferUsage::INDEX
+    });
+
+    // the instances change every frame, but never more of them than one per tile plus the
+    // background, so we allocate that much up front and just overwrite it with write_buffer
+    let instances = state::gen_board_vertices(board);
+    let instance_count = instances.len() as u32;
+    let instance_buf = device.create_buffer(&wgpu::BufferDescriptor {
+      label: Some("Instance Buffer"),
+      size: (MAX_INSTANCES * std::mem::size_of::<Instance>()) as wgpu::BufferAddress,
+      usage: wgpu::BufferUsage::VERTEX | wgpu::BufferUsage::COPY_DST,
+      mapped_at_creation: false
+    });
+    queue.write_buffer(&instance_buf, 0, bytemuck::cast_slice(&instances));
+
+    // load textures
+    let tex_img_data = image::load_from_memory(include_bytes!("../assets/spritesheet.png")).unwrap();
+    let tex_img = tex_img_data.as_rgba8().unwrap();
+    let tex_dimensions = tex_img.dimensions();
+
+    let tex_size = wgpu::Extent3d {
+      width: tex_dimensions.0,
+      height: tex_dimensions.1,
+      depth_or_array_layers: 1
+    };
+
+    let texture = device.create_texture(&wgpu::TextureDescriptor {
+      size: tex_size,
+      mip_level_count: 1,
+      sample_count: 1,
+      dimension: wgpu::TextureDimension::D2,
+      format: wgpu::TextureFormat::Rgba8UnormSrgb,
+      usage: wgpu::TextureUsage::SAMPLED | wgpu::TextureUsage::COPY_DST,
+      label: Some("Layers")
+    });
+
+    queue.write_texture(
+      wgpu::ImageCopyTextureBase {
+        texture: &texture,
+        mip_level: 0,
+        origin: wgpu::Origin3d::ZERO
+      },
+      tex_img,
+      wgpu::ImageDataLayout {
+        offset: 0,
+        bytes_per_row: Some((4 * tex_dimensions.0).try_into().unwrap()),
+        rows_per_image: None
+      },
+      tex_size
+    );
+
+    // create texture view and sampler
+    let tex_view = texture.create_view(&wgpu::TextureViewDescriptor::default());
+    let tex_sampler = device.create_sampler(&wgpu::SamplerDescriptor::default());
+
+    // create bind group
+    let bind_group_layout = device.create_bind_group_layout(&wgpu::BindGroupLayoutDescriptor {
+      label: Some("Bind Group Layout"),
+      entries: &[
+        wgpu::BindGroupLayoutEntry {
+          binding: 0,
+          visibility: wgpu::ShaderStage::FRAGMENT,
+          ty: wgpu::BindingType::Texture {
+            multisampled: false,
+            sample_type: wgpu::TextureSampleType::Float { filterable: false },
+            view_dimension: wgpu::TextureViewDimension::D2
+          },
+          count: None
+        },
+        wgpu::BindGroupLayoutEntry {
+          binding: 1,
+          visibility: wgpu::ShaderStage::FRAGMENT,
+          ty: wgpu::BindingType::Sampler {
+            comparison: false,
+            filtering: true
+          },
+          count: None
+        }
+      ]
+    });
+
+    let bind_group = device.create_bind_group(&wgpu::BindGroupDescriptor {
+      layout: &bind_group_layout,
+      entries: &[
+        wgpu::BindGroupEntry {
+          binding: 0,
+          resource: wgpu::BindingResource::TextureView(&tex_view)
+        },
+        wgpu::BindGroupEntry {
+          binding: 1,
+          resource: wgpu::BindingResource::Sampler(&tex_sampler)
+        }
+      ],
+      label: Some("Bind Group")
+    });
+
+    // create render pipeline
+    let pipeline_layout = device.create_pipeline_layout(&wgpu::PipelineLayoutDescriptor {
+      label: Some("Pipeline Layout"),
+      bind_group_layouts: &[&bind_group_layout],
+      push_constant_ranges: &[]
+    });
+    let swapchain_format = adapter.get_swap_chain_preferred_format(&surface).unwrap();
+    let render_pipeline = device.create_render_pipeline(&wgpu::RenderPipelineDescriptor {
+      label: Some("Render Pipeline"),
+      layout: Some(&pipeline_layout),
+      vertex: wgpu::VertexState {
+        module: &shader,
+        entry_point: "vs_main",
+        buffers: &[
+          wgpu::VertexBufferLayout {
+            array_stride: std::mem::size_of::<Vertex>() as wgpu::BufferAddress,
+            step_mode: wgpu::InputStepMode::Vertex,
+            attributes: &wgpu::vertex_attr_array![0 => Float32x2, 1 => Float32x2]
+          },
+          wgpu::VertexBufferLayout {
+            array_stride: std::mem::size_of::<Instance>() as wgpu::BufferAddress,
+            step_mode: wgpu::InputStepMode::Instance,
+            attributes: &wgpu::vertex_attr_array![2 => Float32x2, 3 => Float32x2, 4 => Uint32, 5 => Uint32]
+          }
+        ]
+      },
+      fragment: Some(wgpu::FragmentState {
+        module: &shader,
+        entry_point: "fs_main",
+        targets: &[wgpu::ColorTargetState {
+          format: swapchain_format,
+          blend: Some(wgpu::BlendState::ALPHA_BLENDING),
+          write_mask: wgpu::ColorWrite::ALL
+        }]
+      }),
+      primitive: wgpu::PrimitiveState::default(),
+      depth_stencil: None,
+      multisample: wgpu::MultisampleState::default()
+    });
+
+    // finally! lets make the swap chain :)
+    let mut sc_desc = wgpu::SwapChainDescriptor {
+      usage: wgpu::TextureUsage::RENDER_ATTACHMENT,
+      format: swapchain_format,
+      width: size.width,
+      height: size.height,
+      present_mode: wgpu::PresentMode::Fifo
+    };
+
+    let mut swap_chain = device.create_swap_chain(&surface, &sc_desc);
+
+    // the board is drawn first, then the win-line highlight on top of it once the game ends;
+    // new visual layers are added here as their own node, instead of editing `render`
+    let mut graph = RenderGraph::new();
+    graph.push(BoardPass);
+    graph.push(WinLinePass::new(&device, swapchain_format));
+
+    return Render {
+      surface, device, queue, swapchain_format, render_pipeline, sc_desc, swap_chain,
+      bind_group, vertex_buf, index_buf, index_count, instance_buf, instance_count, graph
+    };
+
+  }
+
+  /**
+   * Take the current board state, write it into the (already allocated) instance buffer
+   */
+  pub fn update(&mut self, board: &[[state::Tile; 3]; 3]) {
+
+    let instances = state::gen_board_vertices(board);
+    self.instance_count = instances.len() as u32;
+
+    self.queue.write_buffer(&self.instance_buf, 0, bytemuck::cast_slice(&instances));
+
+  }
+
+  /**
+   * Rebuild the swap chain for a new surface size. Called on `WindowEvent::Resized` and
+   * `ScaleFactorChanged`; `render` rebuilds a stale swap chain itself, at the stored size.
+   */
+  pub fn resize(&mut self, new_size: winit::dpi::PhysicalSize<u32>) {
+
+    // a minimised window reports a size of 0, which the swap chain can't be built with
+    if new_size.width == 0 || new_size.height == 0 { return; }
+
+    self.sc_desc.width = new_size.width;
+    self.sc_desc.height = new_size.height;
+    self.swap_chain = self.device.create_swap_chain(&self.surface, &self.sc_desc);
+
+  }
+
+  /**
+   * Render the game :), recording every node of the render graph into a single command buffer.
+   * `winning_line` comes straight from `State` and is only `Some` once the game is won.
+   */
+  pub fn render(&mut self, winning_line: Option<[(usize, usize); 3]>) {
+
+    let frame = match self.swap_chain.get_current_frame() {
+      Ok(frame) => frame.output,
+      // the swap chain is stale (e.g. the surface was resized); rebuild it and skip this frame
+      Err(wgpu::SwapChainError::Lost) | Err(wgpu::SwapChainError::Outdated) => {
+        self.swap_chain = self.device.create_swap_chain(&self.surface, &self.sc_desc);
+        return;
+      },
+      Err(e) => panic!("Failed to acquire next swap chain texture: {:?}", e)
+    };
+    let mut encoder = self.device.create_command_encoder(&wgpu::CommandEncoderDescriptor { label: Some("Encoder") });
+
+    let ctx = RenderContext {
+      queue: &self.queue,
+      render_pipeline: &self.render_pipeline,
+      bind_group: &self.bind_group,
+      vertex_buf: &self.vertex_buf,
+      index_buf: &self.index_buf,
+      index_count: self.index_count,
+      instance_buf: &self.instance_buf,
+      instance_count: self.instance_count,
+      winning_line
+    };
+    self.graph.record(&mut encoder, &frame.view, &ctx);
+
+    // finish rendering and free memory :)
+    self.queue.submit(Some(encoder.finish()));
+
+  }
+
+}
\ No newline at end of file
@@ -0,0 +1,227 @@
+use std::borrow::Cow;
+use bytemuck::{Pod, Zeroable};
+
+/**
+ * One step of a frame's rendering. A `RenderGraph` records its nodes, in order, into a single
+ * command encoder, so a new visual effect is added by writing a node rather than editing
+ * `Render::render` directly.
+ */
+pub trait RenderPassNode {
+  fn record(&self, encoder: &mut wgpu::CommandEncoder, view: &wgpu::TextureView, ctx: &RenderContext);
+}
+
+/**
+ * Handles shared by every node. Built fresh each frame from `Render`'s own fields, so nodes
+ * never have to care how those buffers/pipelines are owned.
+ */
+pub struct RenderContext<'a> {
+  pub queue: &'a wgpu::Queue,
+  pub render_pipeline: &'a wgpu::RenderPipeline,
+  pub bind_group: &'a wgpu::BindGroup,
+  pub vertex_buf: &'a wgpu::Buffer,
+  pub index_buf: &'a wgpu::Buffer,
+  pub index_count: u32,
+  pub instance_buf: &'a wgpu::Buffer,
+  pub instance_count: u32,
+  pub winning_line: Option<[(usize, usize); 3]>
+}
+
+/**
+ * An ordered list of passes, recorded into one command encoder and submitted together.
+ */
+pub struct RenderGraph {
+  nodes: Vec<Box<dyn RenderPassNode>>
+}
+
+impl RenderGraph {
+
+  pub fn new() -> Self {
+    return RenderGraph { nodes: Vec::new() };
+  }
+
+  pub fn push(&mut self, node: impl RenderPassNode + 'static) {
+    self.nodes.push(Box::new(node));
+  }
+
+  pub fn record(&self, encoder: &mut wgpu::CommandEncoder, view: &wgpu::TextureView, ctx: &RenderContext) {
+    for node in self.nodes.iter() {
+      node.record(encoder, view, ctx);
+    }
+  }
+
+}
+
+/**
+ * Clears the surface and draws the board: the background plus every occupied tile, instanced
+ * from `ctx.instance_buf`. This is the clear-then-draw pass `Render::render` used to record inline.
+ */
+pub struct BoardPass;
+
+impl RenderPassNode for BoardPass {
+
+  fn record(&self, encoder: &mut wgpu::CommandEncoder, view: &wgpu::TextureView, ctx: &RenderContext) {
+
+    let mut rpass = encoder.begin_render_pass(&wgpu::RenderPassDescriptor {
+      label: Some("board pass"),
+      color_attachments: &[wgpu::RenderPassColorAttachment {
+        view,
+        resolve_target: None,
+        ops: wgpu::Operations {
+          load: wgpu::LoadOp::Clear(wgpu::Color {
+            r: 1., g: 1., b: 1., a: 1.
+          }),
+          store: true
+        }
+      }],
+      depth_stencil_attachment: None
+    });
+
+    rpass.set_pipeline(ctx.render_pipeline);
+    rpass.set_bind_group(0, ctx.bind_group, &[]);
+    rpass.set_vertex_buffer(0, ctx.vertex_buf.slice(..));
+    rpass.set_vertex_buffer(1, ctx.instance_buf.slice(..));
+    rpass.set_index_buffer(ctx.index_buf.slice(..), wgpu::IndexFormat::Uint16);
+    rpass.draw_indexed(0..ctx.index_count, 0, 0..ctx.instance_count);
+
+  }
+
+}
+
+// a plain, untextured point for the win-line highlight
+#[repr(C)]
+#[derive(Clone, Copy, Pod, Zeroable)]
+struct LineVertex {
+  pos: [f32; 2]
+}
+
+// half the width of the highlight, in clip space
+const LINE_THICKNESS: f32 = 0.05;
+// the most vertices a single winning-line quad ever needs
+const MAX_LINE_VERTICES: usize = 6;
+
+/**
+ * Center of the given tile, in clip space. Matches the tile placement in `state::gen_board_vertices`.
+ */
+fn tile_center(x: usize, y: usize) -> [f32; 2] {
+  return [ -1. + 0.666 * x as f32 + 0.333, 1. - 0.666 * y as f32 - 0.333 ];
+}
+
+/**
+ * Build a thin quad running from the first to the last tile of a winning line (the middle tile
+ * is collinear, so it isn't needed to place the quad).
+ */
+fn gen_win_line_vertices(line: [(usize, usize); 3]) -> Vec<LineVertex> {
+
+  let start = tile_center(line[0].0, line[0].1);
+  let end = tile_center(line[2].0, line[2].1);
+
+  let dir = [ end[0] - start[0], end[1] - start[1] ];
+  let len = (dir[0] * dir[0] + dir[1] * dir[1]).sqrt();
+  let normal = [ -dir[1] / len * LINE_THICKNESS, dir[0] / len * LINE_THICKNESS ];
+
+  let a = [ start[0] + normal[0], start[1] + normal[1] ];
+  let b = [ start[0] - normal[0], start[1] - normal[1] ];
+  let c = [ end[0] - normal[0], end[1] - normal[1] ];
+  let d = [ end[0] + normal[0], end[1] + normal[1] ];
+
+  return vec![
+    LineVertex { pos: a }, LineVertex { pos: b }, LineVertex { pos: c },
+    LineVertex { pos: a }, LineVertex { pos: c }, LineVertex { pos: d }
+  ];
+
+}
+
+/**
+ * Draws a highlight over the winning row/column/diagonal once `State::finished` is set. Reads
+ * the winning triple straight from `RenderContext` every frame, so there's nothing to keep in
+ * sync by hand.
+ */
+pub struct WinLinePass {
+  pipeline: wgpu::RenderPipeline,
+  vertex_buf: wgpu::Buffer
+}
+
+impl WinLinePass {
+
+  pub fn new(device: &wgpu::Device, swapchain_format: wgpu::TextureFormat) -> Self {
+
+    let shader = device.create_shader_module(&wgpu::ShaderModuleDescriptor {
+      label: Some("Win Line Shader"),
+      source: wgpu::ShaderSource::Wgsl(Cow::Borrowed(include_str!("line.wgsl"))),
+      flags: wgpu::ShaderFlags::VALIDATION
+    });
+
+    let pipeline_layout = device.create_pipeline_layout(&wgpu::PipelineLayoutDescriptor {
+      label: Some("Win Line Pipeline Layout"),
+      bind_group_layouts: &[],
+      push_constant_ranges: &[]
+    });
+
+    let pipeline = device.create_render_pipeline(&wgpu::RenderPipelineDescriptor {
+      label: Some("Win Line Pipeline"),
+      layout: Some(&pipeline_layout),
+      vertex: wgpu::VertexState {
+        module: &shader,
+        entry_point: "vs_main",
+        buffers: &[wgpu::VertexBufferLayout {
+          array_stride: std::mem::size_of::<LineVertex>() as wgpu::BufferAddress,
+          step_mode: wgpu::InputStepMode::Vertex,
+          attributes: &wgpu::vertex_attr_array![0 => Float32x2]
+        }]
+      },
+      fragment: Some(wgpu::FragmentState {
+        module: &shader,
+        entry_point: "fs_main",
+        targets: &[wgpu::ColorTargetState {
+          format: swapchain_format,
+          blend: Some(wgpu::BlendState::ALPHA_BLENDING),
+          write_mask: wgpu::ColorWrite::ALL
+        }]
+      }),
+      primitive: wgpu::PrimitiveState::default(),
+      depth_stencil: None,
+      multisample: wgpu::MultisampleState::default()
+    });
+
+    let vertex_buf = device.create_buffer(&wgpu::BufferDescriptor {
+      label: Some("Win Line Vertex Buffer"),
+      size: (MAX_LINE_VERTICES * std::mem::size_of::<LineVertex>()) as wgpu::BufferAddress,
+      usage: wgpu::BufferUsage::VERTEX | wgpu::BufferUsage::COPY_DST,
+      mapped_at_creation: false
+    });
+
+    return WinLinePass { pipeline, vertex_buf };
+
+  }
+
+}
+
+impl RenderPassNode for WinLinePass {
+
+  fn record(&self, encoder: &mut wgpu::CommandEncoder, view: &wgpu::TextureView, ctx: &RenderContext) {
+
+    let line = match ctx.winning_line {
+      Some(line) => line,
+      None => return
+    };
+
+    let vertices = gen_win_line_vertices(line);
+    ctx.queue.write_buffer(&self.vertex_buf, 0, bytemuck::cast_slice(&vertices));
+
+    let mut rpass = encoder.begin_render_pass(&wgpu::RenderPassDescriptor {
+      label: Some("win line pass"),
+      color_attachments: &[wgpu::RenderPassColorAttachment {
+        view,
+        resolve_target: None,
+        ops: wgpu::Operations { load: wgpu::LoadOp::Load, store: true }
+      }],
+      depth_stencil_attachment: None
+    });
+
+    rpass.set_pipeline(&self.pipeline);
+    rpass.set_vertex_buffer(0, self.vertex_buf.slice(..));
+    rpass.draw(0..vertices.len() as u32, 0..1);
+
+  }
+
+}
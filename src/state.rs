@@ -1,8 +1,9 @@
-use crate::render::Vertex;
+use crate::render::Instance;
 
 // size of the tiles in the spritesheet
-const TW: f32 = 1. / 20.;
 const TH: f32 = 1. / 3.;
+// sentinel `state` for the background instance, which isn't a real tile occupant
+const BACKGROUND_STATE: u32 = 3;
 
 /**
  * State of a tile on the game board
@@ -25,18 +26,66 @@ impl Tile {
 
 }
 
+// how long each animation frame is held for, independent of the render rate
+const FRAME_DURATION: std::time::Duration = std::time::Duration::from_millis(33);
+
+// the eight ways to win: three columns, three rows, two diagonals, each as board coordinates
+const WIN_PATTERNS: [[(usize, usize); 3]; 8] = [
+  [ (0, 0), (0, 1), (0, 2) ],
+  [ (1, 0), (1, 1), (1, 2) ],
+  [ (2, 0), (2, 1), (2, 2) ],
+  [ (0, 0), (1, 0), (2, 0) ],
+  [ (0, 1), (1, 1), (2, 1) ],
+  [ (0, 2), (1, 2), (2, 2) ],
+  [ (0, 0), (1, 1), (2, 2) ],
+  [ (2, 0), (1, 1), (0, 2) ]
+];
+
 /**
  * Keeps track of the game's state (turns, etc). Not involved with rendering.
  */
 pub struct State {
   turn: bool, // true = X, false = O
-  finished: bool
+  finished: bool,
+  winning_line: Option<[(usize, usize); 3]>,
+  last_frame: instant::Instant
 }
 
 impl State {
 
   pub fn new() -> Self {
-    return State { turn: true, finished: false };
+    return State { turn: true, finished: false, winning_line: None, last_frame: instant::Instant::now() };
+  }
+
+  /**
+   * The winning row/column/diagonal, once the game has been won. Read by the renderer to draw
+   * the highlight over it.
+   */
+  pub fn winning_line(&self) -> Option<[(usize, usize); 3]> {
+    return self.winning_line;
+  }
+
+  /**
+   * Advance every occupied tile's animation by one frame for each FRAME_DURATION of real time
+   * that has passed, stopping at the last frame (19). This keeps the animation speed consistent
+   * no matter how often we're rendering.
+   */
+  pub fn tick_animations(&mut self, board: &mut [[Tile; 3]; 3]) {
+
+    let elapsed = self.last_frame.elapsed();
+    if elapsed < FRAME_DURATION { return; }
+    // catch up on every frame that's due, carrying the leftover time over to the next tick
+    let steps = (elapsed.as_nanos() / FRAME_DURATION.as_nanos()) as u32;
+    self.last_frame += FRAME_DURATION * steps;
+
+    for row in board.iter_mut() {
+      for tile in row.iter_mut() {
+        if tile.state != 0 {
+          tile.frame = (tile.frame as u32).saturating_add(steps).min(19) as u8;
+        }
+      }
+    }
+
   }
 
   /**
@@ -87,26 +136,21 @@ impl State {
    * check if any patterns for victory have been accomplished
    */
   pub fn check_victory(&mut self, window: &winit::window::Window, board: &[[Tile; 3]; 3]) {
-    
-    // column matches
-    if ( board[0][0].state != 0 && board[0][0].state == board[0][1].state && board[0][1].state == board[0][2].state )
-    || ( board[1][0].state != 0 && board[1][0].state == board[1][1].state && board[1][1].state == board[1][2].state )
-    || ( board[2][0].state != 0 && board[2][0].state == board[2][1].state && board[2][1].state == board[2][2].state )
-    // row matches
-    || ( board[0][0].state != 0 && board[0][0].state == board[1][0].state && board[1][0].state == board[2][0].state )
-    || ( board[0][1].state != 0 && board[0][1].state == board[1][1].state && board[1][1].state == board[2][1].state )
-    || ( board[0][2].state != 0 && board[0][2].state == board[1][2].state && board[1][2].state == board[2][2].state )
-    // diagonal matches
-    || ( board[0][0].state != 0 && board[0][0].state == board[1][1].state && board[1][1].state == board[2][2].state )
-    || ( board[2][0].state != 0 && board[2][0].state == board[1][1].state && board[1][1].state == board[0][2].state ) {
-
-      // a victory!
-      self.finished = true;
-      window.set_title(match self.turn {
-        true => "Congratulations Cross!",
-        false => "Congratulations Knots!"
-      });
 
+    for pattern in WIN_PATTERNS.iter() {
+      let [ a, b, c ] = pattern;
+      let a_state = board[a.0][a.1].state;
+
+      if a_state != 0 && a_state == board[b.0][b.1].state && a_state == board[c.0][c.1].state {
+        // a victory!
+        self.finished = true;
+        self.winning_line = Some(*pattern);
+        window.set_title(match self.turn {
+          true => "Congratulations Cross!",
+          false => "Congratulations Knots!"
+        });
+        return;
+      }
     }
 
   }
@@ -124,59 +168,37 @@ pub fn gen_board() -> [[Tile; 3]; 3] {
   ];
 }
 
-pub fn gen_board_vertices(board: &mut [[Tile; 3]; 3]) -> Vec<Vertex> {
-
-  // simplify things by breaking up the vertices before combining
-  // simple two triangle square for the background, the texture is at the bottom of the sheet
-  let mut background: Vec<Vertex> = vec![
-    Vertex { pos: [ -1., 1. ], tex_coords: [ 0., TH * 2. ] },
-    Vertex { pos: [ -1., -1. ], tex_coords: [ 0., 1. ] },
-    Vertex { pos: [ 1., -1. ], tex_coords: [ TW, 1. ] },
+/**
+ * Build the per-instance data for the current board: one instance for the background, plus
+ * one more for every occupied tile. Each instance just tells the GPU where to place the shared
+ * unit quad and which cell of the spritesheet to sample.
+ */
+pub fn gen_board_vertices(board: &[[Tile; 3]; 3]) -> Vec<Instance> {
 
-    Vertex { pos: [ -1., 1. ], tex_coords: [ 0., TH * 2. ] },
-    Vertex { pos: [ 1., -1. ], tex_coords: [ TW, TH * 3. ] },
-    Vertex { pos: [ 1., 1. ], tex_coords: [ TW, TH * 2. ] },
+  // the background always gets drawn, covering the whole board; the texture is at the bottom of the sheet
+  let mut instances: Vec<Instance> = vec![
+    Instance { offset: [ -1., 1. ], tex_origin: [ 0., TH * 2. ], frame: 0, state: BACKGROUND_STATE }
   ];
 
-  // create a list for the loop to write to
-  let mut tiles: Vec<Vertex> = Vec::new();
-  // loop through the tiles to generate vertices
-  for (x, row) in board.clone().iter().enumerate() {
-    // get each tile
+  // loop through the tiles to generate an instance for each occupied one
+  for (x, row) in board.iter().enumerate() {
     for (y, tile) in row.iter().enumerate() {
-      // if the tile type is zero, no vertices needed
+      // if the tile is empty, no instance needed
       if tile.state != 0 {
         // find the coordinates for the (top left of the) tile
         let tile_x: f32 = -1. + 0.666 * x as f32;
         let tile_y: f32 = 1. - 0.666 * y as f32;
-        // get the coordinates for the texture to use
-        let tex_x: f32 = TW * tile.frame as f32;
-        let tex_y: f32 = TH * (tile.state - 1) as f32;
-
-        // push the new vertices
-        tiles.append(&mut vec![
-          Vertex { pos: [ tile_x, tile_y ], tex_coords: [ tex_x, tex_y ] },
-          Vertex { pos: [ tile_x, tile_y - 0.666 ], tex_coords: [ tex_x, tex_y + TH ] },
-          Vertex { pos: [ tile_x + 0.666, tile_y - 0.666 ], tex_coords: [ tex_x + TW, tex_y + TH ] },
-
-          Vertex { pos: [ tile_x, tile_y ], tex_coords: [ tex_x, tex_y ] },
-          Vertex { pos: [ tile_x + 0.666, tile_y - 0.666 ], tex_coords: [ tex_x + TW, tex_y + TH ] },
-          Vertex { pos: [ tile_x + 0.666, tile_y ], tex_coords: [ tex_x + TW, tex_y ] }
-        ]);
-
-        // if the animation frame isn't 19 (frame 20), update it for the next render
-        if tile.frame < 19 {
-          board[x][y].frame += 1;
-        }
+
+        instances.push(Instance {
+          offset: [ tile_x, tile_y ],
+          tex_origin: [ 0., TH * (tile.state - 1) as f32 ],
+          frame: tile.frame as u32,
+          state: tile.state as u32
+        });
       }
     }
   }
 
-  // make a shared list for all the vertices
-  let mut vertices: Vec<Vertex> = Vec::new();
-  vertices.append(&mut background);
-  vertices.append(&mut tiles);
-
-  return vertices;
+  return instances;
 
 }
\ No newline at end of file